@@ -10,6 +10,8 @@
 //! - Executable running: Runs the built executable after a successful build
 //! - Debouncing: Prevents multiple rebuilds for rapid successive file changes
 //! - Colored output: Provides visually distinct console messages for better readability
+//! - Configurable pipelines: Replace build-and-run with your own `--cargo`/`--exec` steps
+//! - Project config: Settings can be committed to a `cargomon.toml`, with CLI flags winning
 //!
 //! # Usage
 //!
@@ -23,9 +25,7 @@
 //! Then, in your `main.rs` file:
 //!
 //! ```no_run
-//! fn main() {
-//!     cargomon::run();
-//! }
+//! cargomon::run();
 //! ```
 //!
 //! # Command-line Options
@@ -34,6 +34,7 @@
 //!
 //! - `--watch-path` or `-w`: Specifies the directory to watch for changes (default: ".")
 //! - `--debounce-secs` or `-d`: Sets the debounce time in seconds (default: 2)
+//! - `--config`: Path to a `cargomon.toml` config file (default: `./cargomon.toml` if present)
 //!
 //! Example usage:
 //!
@@ -43,36 +44,178 @@
 
 use notify::{Watcher, RecursiveMode, watcher};
 use std::sync::mpsc::channel;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
 use std::time::{Duration, Instant};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use colored::*;
 use structopt::StructOpt;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::io::BufRead;
+use filetime::FileTime;
+use serde::Deserialize;
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+/// How long to wait after a graceful `SIGTERM` before escalating to `SIGKILL`.
+#[cfg(unix)]
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
 
 /// Command-line options for Cargomon
 #[derive(Debug, StructOpt)]
 #[structopt(name = "cargomon", about = "A Rust implementation inspired by nodemon")]
 struct Opt {
-    /// The directory to watch for changes
-    #[structopt(short, long, default_value = ".")]
-    watch_path: String,
+    /// The directory to watch for changes. Defaults to the `watch_path`
+    /// list in `cargomon.toml` (or just `.` if there is none).
+    #[structopt(short, long)]
+    watch_path: Option<String>,
 
-    /// The debounce time in seconds
-    #[structopt(short, long, default_value = "2")]
-    debounce_secs: u64,
+    /// The debounce time in seconds. Defaults to the value in
+    /// `cargomon.toml`, or 2 if there is none.
+    #[structopt(short, long)]
+    debounce_secs: Option<u64>,
+
+    /// Additional glob pattern to ignore, on top of `.gitignore`/`.ignore` and the
+    /// hardcoded `target/`/`.git/` exclusion. May be passed more than once.
+    #[structopt(long = "ignore")]
+    ignore: Vec<String>,
+
+    /// Don't honor `.gitignore`/`.ignore` files when deciding what to watch
+    #[structopt(long)]
+    no_gitignore: bool,
+
+    /// Run the executable to completion on each change instead of restarting
+    /// a long-running process (the old behavior)
+    #[structopt(long)]
+    no_restart: bool,
+
+    /// Which binary to run when the build produces more than one
+    #[structopt(long)]
+    bin: Option<String>,
+
+    /// A command to run as a pipeline step, e.g. `-x "clippy -- -D warnings"`
+    /// or `-x test`. May be passed more than once; steps run in the order
+    /// given, after any `--cargo` steps, and the pipeline aborts on the
+    /// first step that fails. Passing `--exec`/`--cargo` at all replaces the
+    /// default build-and-run pipeline. Unless `--no-restart` is set, the
+    /// *last* step is treated as a long-running process (e.g. `--cargo run`
+    /// starting a server): it's restarted rather than waited on, just like
+    /// the binary in the default pipeline.
+    #[structopt(short = "x", long = "exec")]
+    exec: Vec<String>,
+
+    /// Shortcut for an `--exec` step that runs `cargo <subcommand>`, e.g.
+    /// `--cargo run` or `--cargo "clippy -- -D warnings"`
+    #[structopt(long = "cargo")]
+    cargo: Vec<String>,
+
+    /// Path to a `cargomon.toml` config file. Settings load in layers:
+    /// built-in defaults, then this file, then the flags above, which win.
+    /// List flags (`--ignore`, `--cargo`, `--exec`) add to the file's lists
+    /// rather than replacing them.
+    #[structopt(long)]
+    config: Option<String>,
 
     /// Display help information
     #[structopt(subcommand)]
-    cmd: Option<Command>,
+    cmd: Option<Subcommand>,
 }
 
 #[derive(Debug, StructOpt)]
-enum Command {
+enum Subcommand {
     /// Display detailed help information
     Help,
 }
 
+/// The shape of a `cargomon.toml` project config file, analogous to
+/// `nodemon.json`. Every field is optional so a project only needs to set
+/// what it wants to override.
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    watch_path: Option<Vec<String>>,
+    debounce_secs: Option<u64>,
+    #[serde(default)]
+    ignore: Vec<String>,
+    #[serde(default)]
+    cargo: Vec<String>,
+    #[serde(default)]
+    exec: Vec<String>,
+    bin: Option<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+}
+
+/// Reads and parses the config file at `--config <path>`, or `cargomon.toml`
+/// in the current directory if no path was given and the default exists.
+fn load_config(opt: &Opt) -> Config {
+    let path = match &opt.config {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from("cargomon.toml"),
+    };
+
+    if !path.exists() {
+        if opt.config.is_some() {
+            panic!("Config file not found: {}", path.display());
+        }
+        return Config::default();
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read config file {}: {}", path.display(), e));
+
+    toml::from_str(&contents)
+        .unwrap_or_else(|e| panic!("Failed to parse config file {}: {}", path.display(), e))
+}
+
+/// The fully resolved settings the watch/build/run loop runs with, after
+/// layering built-in defaults, an optional `cargomon.toml`, and CLI flags
+/// (defaults < config file < command-line args).
+struct Settings {
+    watch_paths: Vec<String>,
+    debounce_secs: u64,
+    ignore: Vec<String>,
+    no_gitignore: bool,
+    no_restart: bool,
+    bin: Option<String>,
+    cargo: Vec<String>,
+    exec: Vec<String>,
+    env: HashMap<String, String>,
+}
+
+impl Settings {
+    fn resolve(opt: &Opt) -> Self {
+        let config = load_config(opt);
+
+        let watch_paths = match &opt.watch_path {
+            Some(path) => vec![path.clone()],
+            None => config.watch_path.unwrap_or_else(|| vec![".".to_string()]),
+        };
+
+        let mut ignore = config.ignore;
+        ignore.extend(opt.ignore.iter().cloned());
+
+        let mut cargo = config.cargo;
+        cargo.extend(opt.cargo.iter().cloned());
+
+        let mut exec = config.exec;
+        exec.extend(opt.exec.iter().cloned());
+
+        Settings {
+            watch_paths,
+            debounce_secs: opt.debounce_secs.or(config.debounce_secs).unwrap_or(2),
+            ignore,
+            no_gitignore: opt.no_gitignore,
+            no_restart: opt.no_restart,
+            bin: opt.bin.clone().or(config.bin),
+            cargo,
+            exec,
+            env: config.env,
+        }
+    }
+}
+
 /// Starts the Cargomon file watcher and build/run loop.
 ///
 /// This function sets up a file watcher for the specified directory and its subdirectories.
@@ -97,64 +240,65 @@ enum Command {
 ///
 /// ```no_run
 /// // In your main.rs file:
-/// fn main() {
-///     cargomon::run();
-/// }
+/// cargomon::run();
 /// ```
 pub fn run() {
     let opt = Opt::from_args();
 
-    if let Some(Command::Help) = opt.cmd {
+    if let Some(Subcommand::Help) = opt.cmd {
         display_help();
         return;
     }
 
+    let settings = Settings::resolve(&opt);
+    let ignore_matchers = build_ignore_matchers(&settings);
+    let pipeline = build_pipeline(&settings);
+
     let (tx, rx) = channel();
 
     let mut watcher = watcher(tx, Duration::from_secs(1)).unwrap();
 
-    watcher.watch(&opt.watch_path, RecursiveMode::Recursive).unwrap();
+    for watch_path in &settings.watch_paths {
+        watcher.watch(watch_path, RecursiveMode::Recursive).unwrap();
+    }
 
     println!("{}", "Watching for changes. Press Ctrl+C to exit.".green());
 
     let mut last_build_time = Instant::now();
+    let mut child: Option<Child> = None;
+    let mut fingerprints = Fingerprints::new();
 
     loop {
         match rx.recv() {
-            Ok(_) => {
-                if last_build_time.elapsed() < Duration::from_secs(opt.debounce_secs) {
+            Ok(event) => {
+                if is_ignored_event(&event, &ignore_matchers) {
+                    continue;
+                }
+
+                if !fingerprints.is_dirty(&event) {
+                    continue;
+                }
+
+                if last_build_time.elapsed() < Duration::from_secs(settings.debounce_secs) {
                     continue;
                 }
                 last_build_time = Instant::now();
+                fingerprints.record(&event);
 
-                println!("{}", "Change detected. Rebuilding...".yellow());
-                
-                let output = Command::new("cargo")
-                    .arg("build")
-                    .output()
-                    .expect("Failed to execute cargo build");
-
-                if output.status.success() {
-                    println!("{}", "Build successful. Running the program...".green());
-                    
-                    let executable_path = find_executable();
-                    
-                    let run_output = Command::new(&executable_path)
-                        .output()
-                        .expect("Failed to run the program");
-
-                    if run_output.status.success() {
-                        io::stdout().write_all(&run_output.stdout).unwrap();
-                        println!("{}", "Program executed successfully.".green());
-                    } else {
-                        io::stderr().write_all(&run_output.stderr).unwrap();
-                        println!("{}", "Program execution failed.".red());
+                println!("{}", "Change detected. Running pipeline...".yellow());
+
+                let last_index = pipeline.len().saturating_sub(1);
+                for (index, step) in pipeline.iter().enumerate() {
+                    let is_final = index == last_index;
+                    if !run_step(step, &settings, &mut child, is_final) {
+                        println!(
+                            "{}",
+                            format!("Step `{}` failed. Aborting pipeline.", step_label(step)).red()
+                        );
+                        break;
                     }
-                } else {
-                    println!("{}", "Build failed. Error output:".red());
-                    io::stderr().write_all(&output.stderr).unwrap();
                 }
-                
+
                 println!("\n{}", "Continuing to watch for changes...".green());
             }
             Err(e) => println!("{}", format!("Watch error: {:?}", e).red()),
@@ -162,12 +306,358 @@ pub fn run() {
     }
 }
 
+/// A single step in the pipeline that runs on each detected change.
+enum Step {
+    /// `cargo build`, followed by running (or restarting) the resulting
+    /// binary. This is the default pipeline, kept for backward
+    /// compatibility when no `--exec`/`--cargo` steps are given.
+    BuildAndRun,
+    /// `cargo <args>`, e.g. from `--cargo "clippy -- -D warnings"`.
+    Cargo(Vec<String>),
+    /// An arbitrary command, e.g. from `--exec "echo done"`.
+    Exec(Vec<String>),
+}
+
+/// Builds the pipeline to run on each change. Any `--cargo`/`--exec` step
+/// (from the CLI or `cargomon.toml`) replaces the default build-and-run
+/// pipeline; `cargo` steps run first, in the order given, followed by `exec`
+/// steps, in the order given.
+fn build_pipeline(settings: &Settings) -> Vec<Step> {
+    if settings.cargo.is_empty() && settings.exec.is_empty() {
+        return vec![Step::BuildAndRun];
+    }
+
+    let mut steps = Vec::new();
+    steps.extend(settings.cargo.iter().map(|s| Step::Cargo(split_command(s))));
+    steps.extend(settings.exec.iter().map(|s| Step::Exec(split_command(s))));
+    steps
+}
+
+fn split_command(raw: &str) -> Vec<String> {
+    raw.split_whitespace().map(String::from).collect()
+}
+
+/// A short, human-readable description of a step for failure messages.
+fn step_label(step: &Step) -> String {
+    match step {
+        Step::BuildAndRun => "cargo build".to_string(),
+        Step::Cargo(args) => format!("cargo {}", args.join(" ")),
+        Step::Exec(args) => args.join(" "),
+    }
+}
+
+/// Runs a single pipeline step, returning whether it succeeded. `is_final`
+/// marks the last step in the pipeline, the only one eligible to be treated
+/// as a restartable long-running process (see [`run_command_step`]).
+fn run_step(step: &Step, settings: &Settings, child: &mut Option<Child>, is_final: bool) -> bool {
+    match step {
+        Step::BuildAndRun => run_build_and_run_step(settings, child),
+        Step::Cargo(args) => run_command_step("cargo", args, settings, child, is_final),
+        Step::Exec(args) => match args.split_first() {
+            Some((program, rest)) => run_command_step(program, rest, settings, child, is_final),
+            None => true,
+        },
+    }
+}
+
+/// Runs one `--cargo`/`--exec` pipeline step. Earlier steps in the pipeline
+/// (and the final one under `--no-restart`) are lint/test-style gates: they
+/// block until they exit so the pipeline can abort on failure, the same way
+/// `run_spawned_step` always has. The final step, when restarting is
+/// enabled, is instead treated like the built binary in the default
+/// pipeline: the previous instance is killed and the new one is spawned
+/// with its own process group and streamed stdout/stderr, since it's
+/// expected to be a long-running process (e.g. the server started by
+/// `--cargo run`) rather than one that's meant to exit.
+fn run_command_step(
+    program: &str,
+    args: &[String],
+    settings: &Settings,
+    child: &mut Option<Child>,
+    is_final: bool,
+) -> bool {
+    if is_final && !settings.no_restart {
+        if let Some(previous) = child.take() {
+            stop_child(previous);
+        }
+
+        *child = Some(spawn_streaming(program, args, &settings.env));
+        return true;
+    }
+
+    run_spawned_step(program, args)
+}
+
+fn run_spawned_step(program: &str, args: &[String]) -> bool {
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to execute `{} {}`: {}", program, args.join(" "), e));
+
+    status.success()
+}
+
+/// Runs the default `cargo build` + run/restart step, returning whether the
+/// build succeeded (the run/restart itself never fails the pipeline, since a
+/// crashing program is not a reason to stop watching).
+fn run_build_and_run_step(settings: &Settings, child: &mut Option<Child>) -> bool {
+    match build_and_locate() {
+        BuildOutcome::Success(executables) => {
+            println!("{}", "Build successful. Running the program...".green());
+
+            let executable_path = select_executable(&executables, &settings.bin);
+
+            if settings.no_restart {
+                let run_output = Command::new(&executable_path)
+                    .envs(&settings.env)
+                    .output()
+                    .expect("Failed to run the program");
+
+                if run_output.status.success() {
+                    io::stdout().write_all(&run_output.stdout).unwrap();
+                    println!("{}", "Program executed successfully.".green());
+                } else {
+                    io::stderr().write_all(&run_output.stderr).unwrap();
+                    println!("{}", "Program execution failed.".red());
+                }
+            } else {
+                if let Some(previous) = child.take() {
+                    stop_child(previous);
+                }
+
+                *child = Some(spawn_streaming(&executable_path, &[], &settings.env));
+            }
+
+            true
+        }
+        BuildOutcome::Failure => {
+            println!("{}", "Build failed.".red());
+            false
+        }
+    }
+}
+
+/// Spawns `program` with its own process group (on Unix) and lets its
+/// stdout/stderr stream straight to ours, so long-running processes (the
+/// built binary, or the final step of a custom pipeline) print live instead
+/// of being buffered until they exit.
+fn spawn_streaming(program: &str, args: &[String], env: &HashMap<String, String>) -> Child {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .envs(env)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+
+    #[cfg(unix)]
+    command.process_group(0);
+
+    command.spawn().expect("Failed to spawn the program")
+}
+
+/// Stops a previously spawned child before the next one is started: on Unix,
+/// sends `SIGTERM` to its whole process group, waits up to
+/// [`KILL_GRACE_PERIOD`], then escalates to `SIGKILL` if it's still alive.
+/// On other platforms, falls back to a plain `Child::kill`.
+fn stop_child(mut child: Child) {
+    #[cfg(unix)]
+    {
+        let pid = child.id() as i32;
+        unsafe {
+            libc::kill(-pid, libc::SIGTERM);
+        }
+
+        let deadline = Instant::now() + KILL_GRACE_PERIOD;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = child.try_wait() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+        let _ = child.wait();
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// Tracks each watched file's last-seen mtime and a cheap content hash, so
+/// that events which don't actually change a file's bytes — editors that
+/// touch mtimes on save, swap-file churn, save-without-changes — don't reset
+/// the debounce and trigger a rebuild.
+struct Fingerprints(HashMap<PathBuf, (FileTime, u64)>);
+
+impl Fingerprints {
+    fn new() -> Self {
+        Fingerprints(HashMap::new())
+    }
+
+    /// Returns true if `event` reflects a real content change that should
+    /// trigger the pipeline. Deletions and renames are always treated as
+    /// dirty. This only reads the stored fingerprints; it never updates
+    /// them, so a change that's judged dirty but then swallowed by the
+    /// debounce window isn't silently marked as "seen." Call `record` once
+    /// the pipeline is actually about to run for this event.
+    fn is_dirty(&self, event: &notify::DebouncedEvent) -> bool {
+        use notify::DebouncedEvent::*;
+        match event {
+            Remove(_) | NoticeRemove(_) | Rename(..) => true,
+            Create(path) | Write(path) | Chmod(path) | NoticeWrite(path) => self.is_path_dirty(path),
+            Rescan | Error(..) => false,
+        }
+    }
+
+    fn is_path_dirty(&self, path: &Path) -> bool {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return false,
+        };
+        let mtime = FileTime::from_last_modification_time(&metadata);
+
+        if let Some((last_mtime, last_hash)) = self.0.get(path) {
+            if *last_mtime == mtime {
+                return false;
+            }
+
+            return match hash_file(path) {
+                Some(hash) => hash != *last_hash,
+                None => false,
+            };
+        }
+
+        hash_file(path).is_some()
+    }
+
+    /// Persists the fingerprint(s) touched by `event`, so a future event
+    /// carrying the same content is recognized as already built. Must only
+    /// be called once the pipeline is actually run for this event.
+    fn record(&mut self, event: &notify::DebouncedEvent) {
+        use notify::DebouncedEvent::*;
+        match event {
+            Remove(path) | NoticeRemove(path) => {
+                self.0.remove(path);
+            }
+            Rename(from, to) => {
+                self.0.remove(from);
+                self.record_path(to);
+            }
+            Create(path) | Write(path) | Chmod(path) | NoticeWrite(path) => self.record_path(path),
+            Rescan | Error(..) => {}
+        }
+    }
+
+    fn record_path(&mut self, path: &Path) {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return,
+        };
+        let mtime = FileTime::from_last_modification_time(&metadata);
+
+        if let Some(hash) = hash_file(path) {
+            self.0.insert(path.to_path_buf(), (mtime, hash));
+        }
+    }
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    std::fs::read(path).ok().map(|bytes| seahash::hash(&bytes))
+}
+
+/// Builds one ignore matcher per watch path, each combining that path's own
+/// `.gitignore`/`.ignore` rules (unless `--no-gitignore` is set) with the
+/// hardcoded `target/`/`.git/` exclusion and any `--ignore` globs. Sibling
+/// watch paths aren't necessarily nested under a common root, so a single
+/// matcher rooted at just one of them would match the others' events
+/// against the wrong tree's rules.
+fn build_ignore_matchers(settings: &Settings) -> Vec<Gitignore> {
+    settings
+        .watch_paths
+        .iter()
+        .map(|watch_path| build_ignore_matcher(watch_path, settings))
+        .collect()
+}
+
+fn build_ignore_matcher(watch_path: &str, settings: &Settings) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(watch_path);
+
+    if !settings.no_gitignore {
+        builder.add(Path::new(watch_path).join(".gitignore"));
+        builder.add(Path::new(watch_path).join(".ignore"));
+    }
+
+    for pattern in ["target/", ".git/"] {
+        builder
+            .add_line(None, pattern)
+            .expect("hardcoded ignore pattern is valid");
+    }
+
+    for glob in &settings.ignore {
+        if let Err(e) = builder.add_line(None, glob) {
+            eprintln!("{}", format!("Invalid --ignore pattern {:?}: {}", glob, e).red());
+        }
+    }
+
+    builder.build().expect("failed to build ignore matcher")
+}
+
+/// Returns true if the path carried by a `notify` event matches any of the
+/// per-watch-path ignore matchers and should be dropped before the debounce
+/// check runs.
+fn is_ignored_event(event: &notify::DebouncedEvent, matchers: &[Gitignore]) -> bool {
+    event_path(event)
+        .map(|path| is_ignored_path(path, matchers))
+        .unwrap_or(false)
+}
+
+fn is_ignored_path(path: &Path, matchers: &[Gitignore]) -> bool {
+    // Compare in absolute, `.`-free form so a relative watch root (such as
+    // the default `.`) still prefixes an absolute event path.
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let path: PathBuf = cwd.join(path).components().collect();
+    matchers.iter().any(|matcher| {
+        let root: PathBuf = cwd.join(matcher.path()).components().collect();
+        match path.strip_prefix(&root) {
+            Ok(relative) => matcher
+                .matched_path_or_any_parents(matcher.path().join(relative), path.is_dir())
+                .is_ignore(),
+            Err(_) => false,
+        }
+    })
+}
+
+fn event_path(event: &notify::DebouncedEvent) -> Option<&PathBuf> {
+    use notify::DebouncedEvent::*;
+    match event {
+        NoticeWrite(p) | NoticeRemove(p) | Create(p) | Write(p) | Chmod(p) | Remove(p) => Some(p),
+        Rename(_, to) => Some(to),
+        Rescan | Error(..) => None,
+    }
+}
+
 fn display_help() {
     println!("{}", "Cargomon: A Rust implementation inspired by nodemon".green());
     println!("{}", "Usage: cargomon [OPTIONS] [SUBCOMMAND]".yellow());
     println!("\nOptions:");
-    println!("  -w, --watch-path <PATH>    The directory to watch for changes (default: \".\")")
-    println!("  -d, --debounce-secs <SECS> The debounce time in seconds (default: 2)")
+    println!("  -w, --watch-path <PATH>    The directory to watch for changes (default: \".\")");
+    println!("  -d, --debounce-secs <SECS> The debounce time in seconds (default: 2)");
+    println!("      --ignore <GLOB>        Additional glob pattern to ignore, on top of");
+    println!("                             .gitignore/.ignore and target/.git (repeatable)");
+    println!("      --no-gitignore         Don't honor .gitignore/.ignore files when watching");
+    println!("      --no-restart           Run to completion instead of restarting a");
+    println!("                             long-running process (the old behavior)");
+    println!("      --bin <NAME>           Which binary to run when the build produces more");
+    println!("                             than one");
+    println!("  -x, --exec <CMD>           A command to run as a pipeline step (repeatable)");
+    println!("      --cargo <SUBCOMMAND>   Shortcut for an --exec step that runs");
+    println!("                             `cargo <SUBCOMMAND>` (repeatable)");
+    println!("      --config <PATH>        Path to a cargomon.toml config file");
     println!("  -h, --help                 Print help information");
     println!("  -V, --version              Print version information");
     println!("\nSubcommands:");
@@ -180,103 +670,455 @@ fn display_help() {
     println!("\nExamples:");
     println!("  cargomon");
     println!("  cargomon --watch-path ./src --debounce-secs 5");
+    println!("  cargomon --cargo run");
     println!("  cargomon help");
 }
 
-fn find_executable() -> String {
-    let cargo_toml = std::fs::read_to_string("Cargo.toml").expect("Failed to read Cargo.toml");
-    let package_name = cargo_toml
-        .lines()
-        .find(|line| line.starts_with("name ="))
-        .and_then(|line| line.split('=').nth(1))
-        .map(|name| name.trim().trim_matches('"'))
-        .expect("Failed to find package name in Cargo.toml");
-
-    let mut path = PathBuf::from("target");
-    path.push("debug");
-    path.push(if cfg!(windows) {
-        format!("{}.exe", package_name)
+/// The result of invoking `cargo build` and parsing its JSON message stream.
+enum BuildOutcome {
+    /// The build succeeded; maps each built `bin` target's name to the
+    /// executable path cargo reported for it.
+    Success(HashMap<String, String>),
+    Failure,
+}
+
+/// Runs `cargo build --message-format=json-render-diagnostics`, which has
+/// cargo render compiler diagnostics to stderr as they arrive, collecting the
+/// `executable` path of every `compiler-artifact` message whose
+/// `target.kind` contains `"bin"`. This replaces the old approach of hand
+/// parsing `Cargo.toml`: it works for workspaces, renamed `[[bin]]` targets,
+/// a custom `CARGO_TARGET_DIR`, and release builds, because cargo itself is
+/// reporting the path it chose.
+fn build_and_locate() -> BuildOutcome {
+    let mut child = Command::new("cargo")
+        .arg("build")
+        .arg("--message-format=json-render-diagnostics")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute cargo build");
+
+    let stdout = child.stdout.take().expect("Failed to capture cargo build stdout");
+    let mut executables = HashMap::new();
+
+    for line in io::BufReader::new(stdout).lines() {
+        let line = line.expect("Failed to read cargo build output");
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+
+        match message["reason"].as_str() {
+            Some("compiler-message") => {
+                if let Some(rendered) = message["message"]["rendered"].as_str() {
+                    print!("{}", rendered);
+                }
+            }
+            Some("compiler-artifact") => {
+                let is_bin = message["target"]["kind"]
+                    .as_array()
+                    .map(|kinds| kinds.iter().any(|kind| kind == "bin"))
+                    .unwrap_or(false);
+
+                if let (true, Some(name), Some(executable)) = (
+                    is_bin,
+                    message["target"]["name"].as_str(),
+                    message["executable"].as_str(),
+                ) {
+                    executables.insert(name.to_string(), executable.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let status = child.wait().expect("Failed to wait on cargo build");
+
+    if status.success() {
+        BuildOutcome::Success(executables)
     } else {
-        package_name.to_string()
-    });
+        BuildOutcome::Failure
+    }
+}
+
+/// Picks which built binary to run: the explicit `--bin <name>`, or the sole
+/// binary if the build produced exactly one. Panics with the list of
+/// available binaries otherwise, since there's no safe default to guess.
+fn select_executable(executables: &HashMap<String, String>, bin: &Option<String>) -> String {
+    if let Some(name) = bin {
+        return executables
+            .get(name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "No binary named '{}' was built. Available binaries: {:?}",
+                    name,
+                    available_bin_names(executables)
+                )
+            })
+            .clone();
+    }
+
+    if executables.is_empty() {
+        panic!("no binary was built; cargomon needs a `[[bin]]` target");
+    }
+
+    if executables.len() == 1 {
+        return executables.values().next().unwrap().clone();
+    }
+
+    panic!(
+        "Multiple binaries were built; pick one with --bin <name>. Available binaries: {:?}",
+        available_bin_names(executables)
+    );
+}
 
-    path.to_str().expect("Failed to convert path to string").to_string()
+fn available_bin_names(executables: &HashMap<String, String>) -> Vec<&str> {
+    let mut names: Vec<&str> = executables.keys().map(String::as_str).collect();
+    names.sort();
+    names
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::Write;
-    use tempfile::tempdir;
+    use std::sync::Mutex;
+
+    /// Guards tests that mutate the process-global current directory so they
+    /// don't race each other (or `load_config`'s relative default path lookup
+    /// in other tests running concurrently in the same process).
+    static CWD_GUARD: Mutex<()> = Mutex::new(());
 
     #[test]
-    fn test_find_executable() {
-        // Create a temporary directory
-        let temp_dir = tempdir().unwrap();
-        let temp_path = temp_dir.path();
-
-        // Create a mock Cargo.toml file
-        let cargo_toml_path = temp_path.join("Cargo.toml");
-        let mut cargo_toml = File::create(cargo_toml_path).unwrap();
-        writeln!(cargo_toml, "[package]\nname = \"test_project\"").unwrap();
-
-        // Change the current directory to the temporary directory
-        std::env::set_current_dir(temp_path).unwrap();
-
-        // Run the find_executable function
-        let executable_path = find_executable();
-
-        // Check the result
-        let expected_path = if cfg!(windows) {
-            String::from(r"target\debug\test_project.exe")
-        } else {
-            String::from("target/debug/test_project")
-        };
-        assert_eq!(executable_path, expected_path);
+    fn test_select_executable_single_binary() {
+        let mut executables = HashMap::new();
+        executables.insert("app".to_string(), "target/debug/app".to_string());
+
+        assert_eq!(select_executable(&executables, &None), "target/debug/app");
     }
 
     #[test]
-    #[should_panic(expected = "Failed to read Cargo.toml")]
-    fn test_find_executable_no_cargo_toml() {
-        // Create a temporary directory without a Cargo.toml file
-        let temp_dir = tempdir().unwrap();
-        std::env::set_current_dir(temp_dir.path()).unwrap();
+    fn test_select_executable_by_name() {
+        let mut executables = HashMap::new();
+        executables.insert("app".to_string(), "target/debug/app".to_string());
+        executables.insert("cli".to_string(), "target/debug/cli".to_string());
 
-        // This should panic because there's no Cargo.toml file
-        find_executable();
+        assert_eq!(
+            select_executable(&executables, &Some("cli".to_string())),
+            "target/debug/cli"
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Failed to find package name in Cargo.toml")]
-    fn test_find_executable_invalid_cargo_toml() {
-        // Create a temporary directory
-        let temp_dir = tempdir().unwrap();
-        let temp_path = temp_dir.path();
+    #[should_panic(expected = "No binary named 'missing' was built")]
+    fn test_select_executable_unknown_name() {
+        let mut executables = HashMap::new();
+        executables.insert("app".to_string(), "target/debug/app".to_string());
 
-        // Create an invalid Cargo.toml file
-        let cargo_toml_path = temp_path.join("Cargo.toml");
-        let mut cargo_toml = File::create(cargo_toml_path).unwrap();
-        writeln!(cargo_toml, "[package]\n# Missing name field").unwrap();
+        select_executable(&executables, &Some("missing".to_string()));
+    }
 
-        // Change the current directory to the temporary directory
-        std::env::set_current_dir(temp_path).unwrap();
+    #[test]
+    #[should_panic(expected = "Multiple binaries were built")]
+    fn test_select_executable_ambiguous() {
+        let mut executables = HashMap::new();
+        executables.insert("app".to_string(), "target/debug/app".to_string());
+        executables.insert("cli".to_string(), "target/debug/cli".to_string());
+
+        select_executable(&executables, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "no binary was built; cargomon needs a `[[bin]]` target")]
+    fn test_select_executable_no_binaries() {
+        let executables = HashMap::new();
 
-        // This should panic because the Cargo.toml file is invalid
-        find_executable();
+        select_executable(&executables, &None);
     }
 
     #[test]
     fn test_opt_default_values() {
         let opt = Opt::from_iter(&["test"]);
-        assert_eq!(opt.watch_path, ".");
-        assert_eq!(opt.debounce_secs, 2);
+        assert_eq!(opt.watch_path, None);
+        assert_eq!(opt.debounce_secs, None);
     }
 
     #[test]
     fn test_opt_custom_values() {
         let opt = Opt::from_iter(&["test", "--watch-path", "./src", "--debounce-secs", "5"]);
-        assert_eq!(opt.watch_path, "./src");
-        assert_eq!(opt.debounce_secs, 5);
+        assert_eq!(opt.watch_path, Some("./src".to_string()));
+        assert_eq!(opt.debounce_secs, Some(5));
+    }
+
+    /// Resolves `Settings` from CLI args, via `load_config`'s default
+    /// `cargomon.toml` lookup relative to the process-global cwd. Takes
+    /// `CWD_GUARD` so it can't race `test_settings_resolve_layers_config_under_cli`,
+    /// which points the cwd at a temp dir for the duration of its own guard.
+    fn settings_from_args(args: &[&str]) -> Settings {
+        let _guard = CWD_GUARD.lock().unwrap();
+        Settings::resolve(&Opt::from_iter(args))
+    }
+
+    #[test]
+    fn test_settings_resolve_defaults() {
+        let settings = settings_from_args(&["test"]);
+        assert_eq!(settings.watch_paths, vec!["."]);
+        assert_eq!(settings.debounce_secs, 2);
+        assert!(settings.bin.is_none());
+    }
+
+    #[test]
+    fn test_settings_resolve_cli_overrides_defaults() {
+        let settings = settings_from_args(&["test", "--watch-path", "./src", "--debounce-secs", "5"]);
+        assert_eq!(settings.watch_paths, vec!["./src"]);
+        assert_eq!(settings.debounce_secs, 5);
+    }
+
+    #[test]
+    fn test_build_pipeline_defaults_to_build_and_run() {
+        let settings = settings_from_args(&["test"]);
+        let pipeline = build_pipeline(&settings);
+        assert_eq!(pipeline.len(), 1);
+        assert!(matches!(pipeline[0], Step::BuildAndRun));
+    }
+
+    #[test]
+    fn test_build_pipeline_cargo_then_exec() {
+        let settings = settings_from_args(&[
+            "test",
+            "--cargo",
+            "clippy -- -D warnings",
+            "-x",
+            "echo done",
+        ]);
+        let pipeline = build_pipeline(&settings);
+
+        assert_eq!(pipeline.len(), 2);
+        assert_eq!(
+            step_label(&pipeline[0]),
+            "cargo clippy -- -D warnings"
+        );
+        assert_eq!(step_label(&pipeline[1]), "echo done");
+    }
+
+    #[test]
+    fn test_run_command_step_non_final_blocks_and_reports_failure() {
+        let settings = settings_from_args(&["test"]);
+        let mut child: Option<Child> = None;
+
+        let ok = run_command_step("false", &[], &settings, &mut child, false);
+
+        assert!(!ok);
+        assert!(child.is_none());
+    }
+
+    #[test]
+    fn test_run_command_step_final_step_restarts_without_blocking() {
+        let settings = settings_from_args(&["test"]);
+        let mut child: Option<Child> = None;
+
+        // A long-running step like `--cargo run` must not block the
+        // pipeline: run_command_step should spawn it and return immediately.
+        let ok = run_command_step("sleep", &["5".to_string()], &settings, &mut child, true);
+
+        assert!(ok);
+        let running = child.take().expect("final step should spawn a child");
+        stop_child(running);
+    }
+
+    #[test]
+    fn test_fingerprints_rewrite_with_same_content_is_not_dirty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        std::fs::write(&path, b"fn main() {}").unwrap();
+
+        let mut fingerprints = Fingerprints::new();
+        assert!(fingerprints.is_path_dirty(&path));
+        fingerprints.record_path(&path);
+
+        // Touch the mtime forward without changing the content, as an
+        // editor's save-without-changes or a swap file restore would.
+        let metadata = std::fs::metadata(&path).unwrap();
+        let touched = FileTime::from_unix_time(
+            FileTime::from_last_modification_time(&metadata).unix_seconds() + 1,
+            0,
+        );
+        filetime::set_file_mtime(&path, touched).unwrap();
+
+        assert!(!fingerprints.is_path_dirty(&path));
+    }
+
+    #[test]
+    fn test_fingerprints_content_change_is_dirty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        std::fs::write(&path, b"fn main() {}").unwrap();
+
+        let mut fingerprints = Fingerprints::new();
+        assert!(fingerprints.is_path_dirty(&path));
+        fingerprints.record_path(&path);
+
+        let metadata = std::fs::metadata(&path).unwrap();
+        let touched = FileTime::from_unix_time(
+            FileTime::from_last_modification_time(&metadata).unix_seconds() + 1,
+            0,
+        );
+        std::fs::write(&path, b"fn main() { println!(\"hi\"); }").unwrap();
+        filetime::set_file_mtime(&path, touched).unwrap();
+
+        assert!(fingerprints.is_path_dirty(&path));
+    }
+
+    #[test]
+    fn test_fingerprints_deletion_evicts_and_is_dirty() {
+        let mut fingerprints = Fingerprints::new();
+        let path = PathBuf::from("/nonexistent/does-not-exist.rs");
+        fingerprints
+            .0
+            .insert(path.clone(), (FileTime::from_unix_time(0, 0), 42));
+
+        let event = notify::DebouncedEvent::Remove(path.clone());
+        assert!(fingerprints.is_dirty(&event));
+        fingerprints.record(&event);
+        assert!(!fingerprints.0.contains_key(&path));
+    }
+
+    #[test]
+    fn test_fingerprints_does_not_record_a_change_swallowed_by_debounce() {
+        // This reproduces the bug the fingerprint-recording order used to
+        // have: if a real edit is judged dirty but the caller decides not to
+        // build (e.g. it's inside the debounce window), the fingerprint must
+        // NOT be committed, or the next identical event is wrongly seen as
+        // already built.
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        std::fs::write(&path, b"fn main() {}").unwrap();
+
+        let mut fingerprints = Fingerprints::new();
+        assert!(fingerprints.is_path_dirty(&path));
+        fingerprints.record_path(&path);
+
+        let metadata = std::fs::metadata(&path).unwrap();
+        let touched = FileTime::from_unix_time(
+            FileTime::from_last_modification_time(&metadata).unix_seconds() + 1,
+            0,
+        );
+        std::fs::write(&path, b"fn main() { println!(\"hi\"); }").unwrap();
+        filetime::set_file_mtime(&path, touched).unwrap();
+
+        // The edit is dirty, but the caller (e.g. because it's inside the
+        // debounce window) chooses not to record it.
+        assert!(fingerprints.is_path_dirty(&path));
+
+        // A second identical check must still report dirty, since nothing
+        // was ever built for this content.
+        assert!(fingerprints.is_path_dirty(&path));
+    }
+
+    #[test]
+    fn test_build_ignore_matchers_covers_every_sibling_watch_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        let tests_dir = temp_dir.path().join("tests");
+        std::fs::create_dir(&src_dir).unwrap();
+        std::fs::create_dir(&tests_dir).unwrap();
+        std::fs::write(tests_dir.join(".gitignore"), "fixtures/\n").unwrap();
+
+        let mut settings = settings_from_args(&["test"]);
+        settings.watch_paths = vec![
+            src_dir.to_str().unwrap().to_string(),
+            tests_dir.to_str().unwrap().to_string(),
+        ];
+        let matchers = build_ignore_matchers(&settings);
+
+        // The hardcoded target/ exclusion applies under every watch path, not
+        // just the first one.
+        assert!(is_ignored_path(&src_dir.join("target/debug"), &matchers));
+        assert!(is_ignored_path(&tests_dir.join("target/debug"), &matchers));
+
+        // The .gitignore under `tests/` only applies to paths under `tests/`.
+        assert!(is_ignored_path(&tests_dir.join("fixtures/data.json"), &matchers));
+        assert!(!is_ignored_path(&src_dir.join("fixtures/data.json"), &matchers));
+
+        assert!(!is_ignored_path(&src_dir.join("main.rs"), &matchers));
+    }
+
+    #[test]
+    fn test_event_path_extracts_the_relevant_path_per_variant() {
+        let created = PathBuf::from("src/main.rs");
+        assert_eq!(
+            event_path(&notify::DebouncedEvent::Create(created.clone())),
+            Some(&created)
+        );
+
+        let renamed_to = PathBuf::from("src/renamed.rs");
+        assert_eq!(
+            event_path(&notify::DebouncedEvent::Rename(
+                PathBuf::from("src/old.rs"),
+                renamed_to.clone()
+            )),
+            Some(&renamed_to)
+        );
+
+        assert_eq!(event_path(&notify::DebouncedEvent::Rescan), None);
+    }
+
+    #[test]
+    fn test_is_ignored_event_checks_the_events_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut settings = settings_from_args(&["test"]);
+        settings.watch_paths = vec![temp_dir.path().to_str().unwrap().to_string()];
+        let matchers = build_ignore_matchers(&settings);
+
+        let ignored = notify::DebouncedEvent::Write(temp_dir.path().join("target/debug/app"));
+        let not_ignored = notify::DebouncedEvent::Write(temp_dir.path().join("main.rs"));
+
+        assert!(is_ignored_event(&ignored, &matchers));
+        assert!(!is_ignored_event(&not_ignored, &matchers));
+        assert!(!is_ignored_event(&notify::DebouncedEvent::Rescan, &matchers));
+    }
+
+    #[test]
+    fn test_settings_resolve_layers_config_under_cli() {
+        // The process cwd is global, and `load_config` resolves its default
+        // `cargomon.toml` path relative to it, so this test must hold
+        // `CWD_GUARD` for as long as the cwd is pointed at `temp_dir` to
+        // avoid racing every other test that resolves a relative path.
+        let _guard = CWD_GUARD.lock().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join("cargomon.toml"),
+            r#"
+                watch_path = ["./src", "./tests"]
+                debounce_secs = 10
+                bin = "server"
+
+                [env]
+                RUST_LOG = "debug"
+            "#,
+        )
+        .unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        // `settings_from_args` takes `CWD_GUARD` itself, which would
+        // deadlock since this test already holds it; resolve directly.
+        // The config file sets debounce_secs and bin with nothing on the CLI.
+        let settings = Settings::resolve(&Opt::from_iter(&["test"]));
+        assert_eq!(settings.watch_paths, vec!["./src", "./tests"]);
+        assert_eq!(settings.debounce_secs, 10);
+        assert_eq!(settings.bin, Some("server".to_string()));
+        assert_eq!(settings.env.get("RUST_LOG"), Some(&"debug".to_string()));
+
+        // A CLI flag wins over the same setting in the config file.
+        let settings = Settings::resolve(&Opt::from_iter(&[
+            "test",
+            "--debounce-secs",
+            "1",
+            "--bin",
+            "cli",
+        ]));
+        assert_eq!(settings.debounce_secs, 1);
+        assert_eq!(settings.bin, Some("cli".to_string()));
+
+        std::env::set_current_dir(original_dir).unwrap();
     }
 }